@@ -0,0 +1,135 @@
+
+use color::Color;
+use label;
+use label::FontSize;
+use opengl_graphics::Gl;
+use point::Point;
+use ui_context::UIContext;
+
+/// A block of text wrapped to a maximum width and grouped into pages that
+/// each fit within a maximum height, built on top of the single-line
+/// `label` module.
+pub struct FormattedText {
+    lines: Vec<String>,
+    lines_per_page: uint,
+    line_height: f64,
+    size: FontSize,
+}
+
+impl FormattedText {
+
+    /// Wrap `text` to `max_width` and group the resulting lines into pages
+    /// that each fit within `max_height`.
+    pub fn new(uic: &mut UIContext, text: &str, size: FontSize,
+               max_width: f64, max_height: f64) -> FormattedText {
+        let line_height = size as f64;
+        let lines = wrap_lines(uic, text, size, max_width);
+        let lines_per_page = ::std::cmp::max(1u, (max_height / line_height) as uint);
+        FormattedText {
+            lines: lines,
+            lines_per_page: lines_per_page,
+            line_height: line_height,
+            size: size,
+        }
+    }
+
+    /// The number of pages the text has been broken into. Always at least
+    /// one, even for an empty string.
+    pub fn page_count(&self) -> uint {
+        if self.lines.len() == 0 { 1u }
+        else { (self.lines.len() + self.lines_per_page - 1) / self.lines_per_page }
+    }
+
+    /// Draw the `n`th page (`0`-indexed) with its top-left line at `pos`.
+    /// `n >= page_count()` draws nothing rather than panicking.
+    pub fn draw_page(&self, gl: &mut Gl, uic: &mut UIContext,
+                     pos: Point<f64>, color: Color, n: uint) {
+        let start = n * self.lines_per_page;
+        if start >= self.lines.len() {
+            return;
+        }
+        let end = ::std::cmp::min(start + self.lines_per_page, self.lines.len());
+        for (i, line) in self.lines.slice(start, end).iter().enumerate() {
+            let line_pos = Point::new(pos.x, pos.y + i as f64 * self.line_height, 0.0);
+            label::draw(gl, uic, line_pos, self.size, color, line.as_slice());
+        }
+    }
+
+}
+
+/// Break `text` into lines that each fit within `max_width`, measuring
+/// candidate lines via `label::width` and honoring explicit `'\n'`s as
+/// hard line breaks. A single word wider than `max_width` still occupies
+/// its own line (overflow is allowed) rather than being split mid-word.
+fn wrap_lines(uic: &mut UIContext, text: &str, size: FontSize, max_width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut current = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = if current.len() == 0 { word.to_string() }
+                            else { format!("{} {}", current, word) };
+            if current.len() > 0 && label::width(uic, size, candidate.as_slice()) > max_width {
+                lines.push(current);
+                current = word.to_string();
+            } else {
+                current = candidate;
+            }
+        }
+        lines.push(current);
+    }
+    lines
+}
+
+
+/// A context on which the builder pattern can be implemented.
+pub struct FormattedTextContext<'a> {
+    uic: &'a mut UIContext,
+    text: &'a str,
+    size: FontSize,
+    max_width: f64,
+    max_height: f64,
+}
+
+impl<'a> FormattedTextContext<'a> {
+    /// A builder method for specifying font_size.
+    pub fn size(self, size: FontSize) -> FormattedTextContext<'a> {
+        FormattedTextContext { size: size, ..self }
+    }
+
+    /// A builder method for specifying the maximum width a line may reach
+    /// before wrapping.
+    pub fn max_width(self, max_width: f64) -> FormattedTextContext<'a> {
+        FormattedTextContext { max_width: max_width, ..self }
+    }
+
+    /// A builder method for specifying the maximum visible height of a
+    /// single page.
+    pub fn max_height(self, max_height: f64) -> FormattedTextContext<'a> {
+        FormattedTextContext { max_height: max_height, ..self }
+    }
+
+    /// Finish the builder, wrapping and paginating the text.
+    pub fn build(self) -> FormattedText {
+        FormattedText::new(self.uic, self.text, self.size, self.max_width, self.max_height)
+    }
+}
+
+pub trait FormattedTextBuilder<'a> {
+    /// A formatted_text builder method to be implemented on the UIContext.
+    fn formatted_text(&'a mut self, text: &'a str) -> FormattedTextContext<'a>;
+}
+
+impl<'a> FormattedTextBuilder<'a> for UIContext {
+
+    /// A formatted_text builder method to be implemented on the UIContext.
+    fn formatted_text(&'a mut self, text: &'a str) -> FormattedTextContext<'a> {
+        FormattedTextContext {
+            uic: self,
+            text: text,
+            size: 24u32,
+            max_width: 256.0,
+            max_height: 256.0,
+        }
+    }
+
+}
@@ -0,0 +1,39 @@
+
+/// Non-character keys that widgets care about for navigation. Typed digits
+/// and other printable characters arrive separately via
+/// `KeyboardInput::entered_text`.
+#[deriving(PartialEq, Eq, Clone, Show)]
+pub enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    Backspace,
+    Return,
+    Escape,
+}
+
+/// The keyboard events that occurred since the last frame, collected by the
+/// owning application and handed to `UIContext` so widgets can read them
+/// during `draw`.
+#[deriving(Clone)]
+pub struct KeyboardInput {
+    pub pressed_keys: Vec<Key>,
+    pub entered_text: String,
+}
+
+impl KeyboardInput {
+    /// An empty `KeyboardInput`, as at the start of a frame with no events.
+    pub fn new() -> KeyboardInput {
+        KeyboardInput {
+            pressed_keys: Vec::new(),
+            entered_text: String::new(),
+        }
+    }
+
+    /// Discard this frame's events, ready to collect the next frame's.
+    pub fn clear(&mut self) {
+        self.pressed_keys.clear();
+        self.entered_text.clear();
+    }
+}
@@ -33,22 +33,22 @@ pub fn draw(gl: &mut Gl,
                     .trans(pos.x, pos.y + size as f64);
     for ch in text.chars() {
         let character = uic.get_character(size, ch);
-        context.trans((x + character.bitmap_glyph.left()) as f64,
-                      (y - character.bitmap_glyph.top()) as f64)
+        context.trans((x + character.left) as f64,
+                      (y - character.top) as f64)
                         .image(&character.texture)
                         .rgba(r, g, b, a)
                         .draw(gl);
-        x += (character.glyph.advance().x >> 16) as i32;
-        y += (character.glyph.advance().y >> 16) as i32;
+        x += character.advance_x;
+        y += character.advance_y;
     }
 }
 
 /// Determine the pixel width of the final text bitmap.
 #[inline]
 pub fn width(uic: &mut UIContext, size: FontSize, text: &str) -> f64 {
-    text.chars().fold(0u32, |a, ch| {
+    text.chars().fold(0i32, |a, ch| {
         let character = uic.get_character(size, ch);
-        a + (character.glyph.advance().x >> 16) as u32
+        a + character.advance_x
     }) as f64
 }
 
@@ -8,6 +8,7 @@ use graphics::{
     Draw,
     RelativeTransform2d,
 };
+use keyboard;
 use label;
 use label::FontSize;
 use mouse_state::{
@@ -24,6 +25,7 @@ use utils::{
     compare_f64s,
 };
 use ui_context::{
+    Hitboxed,
     UIID,
     UIContext,
 };
@@ -49,6 +51,12 @@ pub enum State {
     Normal,
     Highlighted(Element),
     Clicked(Element),
+    /// Holds keyboard focus on the given `Element` after the mouse button
+    /// that clicked it has been released, so arrow-key navigation and typed
+    /// digits keep landing on that slot for as long as it's selected.
+    /// Entered from `Clicked` on mouse-up and cleared by clicking elsewhere
+    /// or pressing `Escape`.
+    Selected(Element),
 }
 
 widget_fns!(NumberDialer, State, NumberDialer(Normal))
@@ -160,14 +168,21 @@ fn is_over(pos: Point<f64>,
 }
 
 /// Check and return the current state of the NumberDialer.
+///
+/// A click-to-select model: releasing the mouse button over an element
+/// that was `Clicked` hands the widget persistent `Selected` state that
+/// outlives the mouse button, so `apply_keyboard_input` keeps routing keys
+/// to that slot after the user lets go of the mouse. Selection is only
+/// cleared by clicking outside the widget entirely or by `Escape` (handled
+/// in `apply_keyboard_input`, since `get_new_state` never sees key events).
 #[inline]
 fn get_new_state(is_over_elem: Option<Element>,
                  prev: State,
                  mouse: MouseState) -> State {
     match (is_over_elem, prev, mouse.left) {
         (Some(_), Normal, Down) => Normal,
-        (Some(elem), _, Up) => Highlighted(elem),
         (Some(elem), Highlighted(_), Down) => Clicked(elem),
+        (Some(elem), Selected(_), Down) => Clicked(elem),
         (Some(_), Clicked(p_elem), Down) => {
             match p_elem {
                 ValueGlyph(idx, _) => Clicked(ValueGlyph(idx, mouse.pos.y)),
@@ -180,6 +195,12 @@ fn get_new_state(is_over_elem: Option<Element>,
                 _ => Clicked(p_elem),
             }
         },
+        (Some(_), Clicked(p_elem), Up) => Selected(p_elem),
+        (None, Clicked(p_elem), Up) => Selected(p_elem),
+        (Some(_), Selected(p_elem), Up) => Selected(p_elem),
+        (None, Selected(p_elem), Up) => Selected(p_elem),
+        (None, Selected(_), Down) => Normal,
+        (Some(elem), _, Up) => Highlighted(elem),
         _ => Normal,
     }
 }
@@ -220,6 +241,80 @@ fn get_new_value<T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToStr
             
 }
 
+/// Apply a digit typed into the active `ValueGlyph` slot, replacing that
+/// digit in the value's string representation and re-clamping to
+/// `[min, max]`. Typing over the decimal point, or an index past the end
+/// of `val_string`, leaves `val` unchanged. Returns the clamped value
+/// alongside the digit-substituted string so a caller folding multiple
+/// digits from the same frame can thread it into the next call instead of
+/// reusing the stale pre-frame `val_string` for every digit.
+#[inline]
+fn apply_typed_digit<T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
+(val: T, min: T, max: T, idx: uint, digit: char, val_string: &String) -> (T, String) {
+    let mut chars: Vec<char> = val_string.as_slice().chars().collect();
+    if idx >= chars.len() || chars[idx] == '.' {
+        return (val, val_string.clone());
+    }
+    chars[idx] = digit;
+    let new_string = String::from_chars(chars.as_slice());
+    let new_val = match from_str::<f64>(new_string.as_slice()) {
+        Some(new_val_f) => {
+            let clamped = clamp(new_val_f, min.to_f64().unwrap(), max.to_f64().unwrap());
+            FromPrimitive::from_f64(clamped).unwrap()
+        },
+        None => val,
+    };
+    (new_val, new_string)
+}
+
+/// Fold this frame's keyboard events into the `Selected(ValueGlyph(..))`
+/// state: left/right arrows move the active digit slot, up/down arrows
+/// nudge the active digit by its place value (via `get_new_value`), typed
+/// digits overwrite the active slot directly, and `Escape` clears the
+/// selection back to `Normal`. Gated on `Selected` rather than `Clicked`
+/// so typing keeps working after the mouse button that originally
+/// selected the slot has been released. When `text` carries more than one
+/// digit in a single frame (e.g. coalesced input events under a frame-rate
+/// drop), each digit is applied against the previous digit's result rather
+/// than the frame's stale pre-input `val_string`, so none of them are lost.
+#[inline]
+fn apply_keyboard_input<T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
+(state: State, keys: &[keyboard::Key], text: &str,
+ val: T, min: T, max: T, val_string: &String) -> (State, T) {
+    if let Selected(_) = state {
+        if keys.iter().any(|&key| key == keyboard::Escape) {
+            return (Normal, val);
+        }
+    }
+    match state {
+        Selected(ValueGlyph(idx, y)) => {
+            let mut idx = idx;
+            let mut val = val;
+            let len = val_string.len();
+            for &key in keys.iter() {
+                match key {
+                    keyboard::Left => if idx > 0u { idx -= 1u },
+                    keyboard::Right => if idx + 1u < len { idx += 1u },
+                    keyboard::Up => val = get_new_value(val, min, max, idx, Less, val_string),
+                    keyboard::Down => val = get_new_value(val, min, max, idx, Greater, val_string),
+                    keyboard::Backspace | keyboard::Return | keyboard::Escape => (),
+                }
+            }
+            let mut current_string = val_string.clone();
+            for digit in text.chars() {
+                if digit >= '0' && digit <= '9' {
+                    let (new_val, new_string) = apply_typed_digit(val, min, max, idx, digit,
+                                                                   &current_string);
+                    val = new_val;
+                    current_string = new_string;
+                }
+            }
+            (Selected(ValueGlyph(idx, y)), val)
+        },
+        _ => (state, val),
+    }
+}
+
 /*
 /// Return a suitable font size for the given pad height.
 fn get_font_size(pad_height: f64) -> FontSize {
@@ -261,7 +356,7 @@ fn draw_value_string(win_w: f64,
                 },
                 _ => (),
             },
-            Clicked(elem) => match elem {
+            Clicked(elem) | Selected(elem) => match elem {
                 ValueGlyph(idx, _) => {
                     let context_slot_y = slot_y - (pos.y + size as f64);
                     let rect_color = if idx == i { rect_color.clicked() }
@@ -273,9 +368,9 @@ fn draw_value_string(win_w: f64,
             },
             _ => (),
         };
-        let x_shift = half_slot_w - (character.glyph.advance().x >> 16) as f64 / 2.0;
-        context.trans((x + character.bitmap_glyph.left() + x_shift as i32) as f64,
-                      (y - character.bitmap_glyph.top()) as f64)
+        let x_shift = half_slot_w - character.advance_x as f64 / 2.0;
+        context.trans((x + character.left + x_shift as i32) as f64,
+                      (y - character.top) as f64)
                         .image(&character.texture)
                         .rgba(font_r, font_g, font_b, font_a)
                         .draw(gl);
@@ -347,6 +442,15 @@ impl_labelable!(NumberDialerContext, T)
 impl_positionable!(NumberDialerContext, T)
 impl_shapeable!(NumberDialerContext, T)
 
+impl<'a, T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
+Hitboxed for NumberDialerContext<'a, T> {
+    /// Register this frame's bounding box with the shared hitbox registry.
+    /// Must be called for every widget before any widget's `draw`.
+    fn register_hitbox(&mut self) {
+        self.uic.insert_hitbox(self.ui_id, self.pos, self.width, self.height);
+    }
+}
+
 impl<'a, T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
 ::draw::Drawable for NumberDialerContext<'a, T> {
     #[inline]
@@ -372,11 +476,20 @@ impl<'a, T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
         let label_x = self.pos.x + (self.width - (label_w + val_string_w)) / 2.0;
         let label_y = self.pos.y + (self.height - font_size as f64) / 2.0;
         let l_pos = Point::new(label_x, label_y, 0.0);
-        let is_over_elem = is_over(self.pos, frame_w, mouse.pos,
-                                   self.width, self.height,
-                                   l_pos, label_w, label_h,
-                                   val_string_w, val_string_h,
-                                   val_string.len());
+
+        // Hitbox registration happens in the `register_hitbox` pass (see
+        // `Hitboxed`), which the app runs across every widget before any
+        // widget's `draw` is called, so this reflects the full frame's
+        // stacking order rather than only the widgets drawn so far.
+        let is_over_elem = if self.uic.is_topmost_hitbox(self.ui_id) {
+            is_over(self.pos, frame_w, mouse.pos,
+                   self.width, self.height,
+                   l_pos, label_w, label_h,
+                   val_string_w, val_string_h,
+                   val_string.len())
+        } else {
+            None
+        };
         let new_state = get_new_state(is_over_elem, state, mouse);
         let color = match self.maybe_color { Some(color) => color, None => Default::default() };
 
@@ -393,8 +506,9 @@ impl<'a, T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
             },
         };
 
-        // Determine new value from the initial state and the new state.
-        let new_val = match (state, new_state) {
+        // Determine new value from the initial state and the new state,
+        // first from dragging a `ValueGlyph` slot with the mouse...
+        let mouse_val = match (state, new_state) {
             (Clicked(elem), Clicked(new_elem)) => {
                 match (elem, new_elem) {
                     (ValueGlyph(idx, y), ValueGlyph(_, new_y)) => {
@@ -405,6 +519,13 @@ impl<'a, T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
             }, _ => self.value,
         };
 
+        // ...then fold in any keyboard navigation/entry on the active slot.
+        let keys = self.uic.get_keyboard_input().pressed_keys.clone();
+        let text = self.uic.get_keyboard_input().entered_text.clone();
+        let (new_state, new_val) = apply_keyboard_input(new_state, keys.as_slice(),
+                                                         text.as_slice(), mouse_val,
+                                                         self.min, self.max, &val_string);
+
         // If the value has changed, create a new string for val_string.
         if self.value != new_val {
             val_string = create_val_string(new_val, val_string_len, self.precision)
@@ -423,7 +544,7 @@ impl<'a, T: Num + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
         // Call the `callback` with the new value if the mouse is pressed/released
         // on the widget or if the value has changed.
         if self.value != new_val || match (state, new_state) {
-            (Highlighted(_), Clicked(_)) | (Clicked(_), Highlighted(_)) => true,
+            (Highlighted(_), Clicked(_)) | (Clicked(_), Selected(_)) => true,
             _ => false,
         } {
             match self.maybe_callback {
@@ -0,0 +1,368 @@
+
+use color::Color;
+use graphics::{
+    Context,
+    AddColor,
+    AddImage,
+    Draw,
+    RelativeTransform2d,
+};
+use keyboard;
+use label;
+use label::FontSize;
+use mouse_state::{
+    MouseState,
+    Up,
+    Down,
+};
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use std::default::Default;
+use ui_context::{
+    Hitboxed,
+    UIID,
+    UIContext,
+};
+use widget::SecretEntry;
+
+/// The glyph drawn in place of every character in the buffer that isn't
+/// currently being revealed.
+pub const MASK_CHAR: char = '\u{2022}';
+
+/// The maximum number of characters a `SecretBuffer` will hold.
+/// `SecretBuffer::new` reserves exactly this much capacity up front so
+/// `push` never triggers a `Vec` reallocation, which would otherwise hand
+/// the old, still-plaintext-holding allocation back to the allocator
+/// unzeroed. Characters typed past this limit are dropped.
+const MAX_SECRET_LEN: uint = 256u;
+
+/// The buffer of characters backing a `SecretEntry`, owned by the
+/// application (the same way a `NumberDialer`'s value is owned by its
+/// caller rather than by conrod). Characters are wiped in place, rather
+/// than left for the allocator to eventually overwrite, the moment they're
+/// no longer needed.
+pub struct SecretBuffer {
+    chars: Vec<char>,
+}
+
+impl SecretBuffer {
+    /// Construct an empty buffer with `MAX_SECRET_LEN` capacity reserved
+    /// up front, so `push` never reallocates and abandons an old,
+    /// unzeroed allocation still holding previously-typed characters.
+    pub fn new() -> SecretBuffer {
+        SecretBuffer { chars: Vec::with_capacity(MAX_SECRET_LEN) }
+    }
+
+    /// The number of characters currently entered.
+    pub fn len(&self) -> uint { self.chars.len() }
+
+    /// Borrow the entered characters.
+    pub fn as_slice(&self) -> &[char] { self.chars.as_slice() }
+
+    /// Append a character to the end of the buffer, unless it's already at
+    /// `MAX_SECRET_LEN` (silently dropping the character rather than
+    /// reallocating and risking an unzeroed abandoned allocation).
+    pub fn push(&mut self, ch: char) {
+        if self.chars.len() < MAX_SECRET_LEN {
+            self.chars.push(ch);
+        }
+    }
+
+    /// Wipe the last character in place and remove it from the buffer, if any.
+    pub fn pop(&mut self) {
+        if let Some(last) = self.chars.last_mut() { *last = '\0'; }
+        self.chars.pop();
+    }
+
+    /// Wipe every character in place and empty the buffer.
+    pub fn clear(&mut self) {
+        for ch in self.chars.iter_mut() { *ch = '\0'; }
+        self.chars.clear();
+    }
+
+    /// Copy the buffer out as a plaintext `String`, e.g. to hand to
+    /// `on_submit`. The caller is responsible for not retaining it longer
+    /// than necessary.
+    pub fn to_plaintext(&self) -> String {
+        self.chars.iter().map(|&ch| ch).collect()
+    }
+}
+
+impl Drop for SecretBuffer {
+    /// Wipe the backing characters rather than trusting that the allocator
+    /// won't hand this memory straight back out unzeroed.
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Represents the specific elements that the `SecretEntry` is made up of.
+/// Mirrors `NumberDialer`'s per-slot `Element`/`State` model.
+#[deriving(Show, PartialEq, Clone)]
+pub enum Element {
+    Rect,
+    /// A single character slot at `uint` index.
+    CharSlot(uint),
+}
+
+/// Represents the state of the SecretEntry widget.
+#[deriving(PartialEq, Clone)]
+pub enum State {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element),
+    /// Holds keyboard focus on the given `Element` after the mouse button
+    /// that clicked it has been released, mirroring `NumberDialer`'s
+    /// `Selected` state so typed characters keep landing after the user
+    /// lets go of the mouse. Cleared by clicking elsewhere or `Escape`.
+    Selected(Element),
+}
+
+widget_fns!(SecretEntry, State, SecretEntry(Normal))
+
+/// Return the dimensions of a character slot.
+fn slot_width(size: FontSize) -> f64 {
+    (size as f64 * 0.75).floor() as f64
+}
+
+/// Determine if the cursor is over the `SecretEntry` and if so, which slot.
+#[inline]
+fn is_over(pos: Point<f64>, mouse_pos: Point<f64>, rect_w: f64, rect_h: f64,
+          slot_w: f64, num_slots: uint) -> Option<Element> {
+    match rectangle::is_over(pos, mouse_pos, rect_w, rect_h) {
+        false => None,
+        true => {
+            let mut slot_pos = pos;
+            for i in range(0u, num_slots) {
+                if rectangle::is_over(slot_pos, mouse_pos, slot_w, rect_h) {
+                    return Some(CharSlot(i));
+                }
+                slot_pos.x += slot_w;
+            }
+            Some(Rect)
+        },
+    }
+}
+
+/// Check and return the current state of the `SecretEntry`.
+///
+/// A click-to-select model: releasing the mouse button over an element
+/// that was `Clicked` hands the widget persistent `Selected` state that
+/// outlives the mouse button, so typed characters keep landing in the
+/// buffer after the user lets go of the mouse. Mirrors `NumberDialer`'s
+/// `get_new_state`; selection is only cleared by clicking outside the
+/// widget entirely or by `Escape` (handled in `draw`, since this function
+/// never sees key events).
+#[inline]
+fn get_new_state(is_over_elem: Option<Element>, prev: State, mouse: MouseState) -> State {
+    match (is_over_elem, prev, mouse.left) {
+        (Some(_), Normal, Down) => Normal,
+        (Some(elem), Highlighted(_), Down) => Clicked(elem),
+        (Some(elem), Selected(_), Down) => Clicked(elem),
+        (Some(_), Clicked(p_elem), Down) => Clicked(p_elem),
+        (None, Clicked(p_elem), Down) => Clicked(p_elem),
+        (Some(_), Clicked(p_elem), Up) => Selected(p_elem),
+        (None, Clicked(p_elem), Up) => Selected(p_elem),
+        (Some(elem), Selected(_), Up) => Selected(elem),
+        (None, Selected(p_elem), Up) => Selected(p_elem),
+        (None, Selected(_), Down) => Normal,
+        (Some(elem), _, Up) => Highlighted(elem),
+        _ => Normal,
+    }
+}
+
+/// Draw the masked character slots, revealing the last character in the
+/// buffer in place rather than its mask glyph when `reveal_last` is set.
+/// Every slot is `slot_width(size)` wide whatever glyph it holds, so layout
+/// never varies with the secret. The revealed character, if any, is rasterized through
+/// `get_character_uncached` rather than `get_character` so it never enters
+/// `UIContext`'s shared, unbounded `glyph_cache` — a plaintext character
+/// cached there would outlive `SecretBuffer::clear`'s zeroing.
+#[inline]
+fn draw_masked_string(win_w: f64, win_h: f64, gl: &mut Gl, uic: &mut UIContext,
+                      pos: Point<f64>, size: FontSize, color: Color,
+                      num_chars: uint, last_char: Option<char>, reveal_last: bool) {
+    let (r, g, b, a) = color.as_tuple();
+    let context = Context::abs(win_w, win_h).trans(pos.x, pos.y + size as f64);
+    let slot_w = slot_width(size);
+    let mut x = 0;
+    for i in range(0u, num_chars) {
+        if reveal_last && i + 1u == num_chars {
+            let ch = last_char.unwrap_or(MASK_CHAR);
+            let character = uic.get_character_uncached(size, ch);
+            draw_glyph(&context, gl, &character, x, slot_w, r, g, b, a);
+        } else {
+            let character = uic.get_character(size, MASK_CHAR);
+            draw_glyph(&context, gl, character, x, slot_w, r, g, b, a);
+        }
+        x += slot_w as i32;
+    }
+}
+
+/// Draw a single rasterized glyph, horizontally centered within `slot_w`.
+#[inline]
+fn draw_glyph(context: &Context, gl: &mut Gl, character: &::ui_context::CachedGlyph,
+             x: i32, slot_w: f64, r: f32, g: f32, b: f32, a: f32) {
+    let x_shift = (slot_w - character.advance_x as f64) / 2.0;
+    context.trans((x + character.left + x_shift as i32) as f64,
+                  (0 - character.top) as f64)
+                    .image(&character.texture)
+                    .rgba(r, g, b, a)
+                    .draw(gl);
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct SecretEntryContext<'a> {
+    uic: &'a mut UIContext,
+    ui_id: UIID,
+    buffer: &'a mut SecretBuffer,
+    pos: Point<f64>,
+    width: f64,
+    height: f64,
+    size: FontSize,
+    maybe_color: Option<Color>,
+    reveal_last: bool,
+    maybe_on_submit: Option<|&str|:'a>,
+}
+
+impl<'a> SecretEntryContext<'a> {
+    /// A builder method for specifying font_size.
+    pub fn size(self, size: FontSize) -> SecretEntryContext<'a> {
+        SecretEntryContext { size: size, ..self }
+    }
+
+    /// A builder method toggling whether the last character in the buffer
+    /// is shown in the clear instead of masked. While set, that character
+    /// stays revealed until another is typed or it is removed; the widget
+    /// does not hide it again after a delay.
+    pub fn reveal_last_typed(self, reveal_last: bool) -> SecretEntryContext<'a> {
+        SecretEntryContext { reveal_last: reveal_last, ..self }
+    }
+
+    /// A builder method for registering a callback to be fired with the
+    /// entered secret when the `Return` key is pressed.
+    pub fn on_submit(self, callback: |&str|:'a) -> SecretEntryContext<'a> {
+        SecretEntryContext { maybe_on_submit: Some(callback), ..self }
+    }
+}
+
+pub trait SecretEntryBuilder<'a> {
+    /// A secret_entry builder method to be implemented on the UIContext.
+    fn secret_entry(&'a mut self, ui_id: UIID,
+                    buffer: &'a mut SecretBuffer) -> SecretEntryContext<'a>;
+}
+
+impl<'a> SecretEntryBuilder<'a> for UIContext {
+
+    /// A secret_entry builder method to be implemented on the UIContext.
+    fn secret_entry(&'a mut self, ui_id: UIID,
+                    buffer: &'a mut SecretBuffer) -> SecretEntryContext<'a> {
+        SecretEntryContext {
+            uic: self,
+            ui_id: ui_id,
+            buffer: buffer,
+            pos: Point::new(0.0, 0.0, 0.0),
+            width: 128.0,
+            height: 48.0,
+            size: 24u32,
+            maybe_color: None,
+            reveal_last: false,
+            maybe_on_submit: None,
+        }
+    }
+
+}
+
+impl_colorable!(SecretEntryContext)
+impl_positionable!(SecretEntryContext)
+impl_shapeable!(SecretEntryContext)
+
+impl<'a> Hitboxed for SecretEntryContext<'a> {
+    /// Register this frame's bounding box with the shared hitbox registry.
+    /// Must be called for every widget before any widget's `draw`.
+    fn register_hitbox(&mut self) {
+        self.uic.insert_hitbox(self.ui_id, self.pos, self.width, self.height);
+    }
+}
+
+impl<'a> ::draw::Drawable for SecretEntryContext<'a> {
+    #[inline]
+    /// Draw the secret_entry. Typed characters are appended to the backing
+    /// `SecretBuffer` and masked on screen; pressing `Return` fires
+    /// `on_submit` with the entered secret and clears the buffer.
+    fn draw(&mut self, gl: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state();
+        let slot_w = slot_width(self.size);
+
+        // Hitbox registration happens in the `register_hitbox` pass (see
+        // `Hitboxed`), which the app runs across every widget before any
+        // widget's `draw` is called, so this is arbitrated against the
+        // full frame's stacking order rather than this widget's own rect,
+        // the same as `NumberDialer`.
+        let is_over_elem = if self.uic.is_topmost_hitbox(self.ui_id) {
+            is_over(self.pos, mouse.pos, self.width, self.height,
+                   slot_w, self.buffer.len())
+        } else {
+            None
+        };
+        let new_state = get_new_state(is_over_elem, state, mouse);
+        let color = match self.maybe_color { Some(color) => color, None => Default::default() };
+
+        // Draw the widget rectangle.
+        rectangle::draw(self.uic.win_w, self.uic.win_h, gl, rectangle::Normal,
+                        self.pos, self.width, self.height, None, color);
+
+        // Fold this frame's keyboard events into the buffer while a slot is
+        // selected, reusing `NumberDialer`'s digit-slot interaction model.
+        // Gated on `Selected` rather than `Clicked` so typing keeps working
+        // after the mouse button that originally selected the field has
+        // been released — `Escape` clears the selection back to `Normal`
+        // without touching the buffer.
+        let mut new_state = new_state;
+        if let Selected(_) = new_state {
+            let keys = self.uic.get_keyboard_input().pressed_keys.clone();
+            let text = self.uic.get_keyboard_input().entered_text.clone();
+            if keys.iter().any(|&key| key == keyboard::Escape) {
+                new_state = Normal;
+            } else {
+                for &key in keys.iter() {
+                    match key {
+                        keyboard::Backspace => self.buffer.pop(),
+                        keyboard::Return => {
+                            let plaintext = self.buffer.to_plaintext();
+                            match self.maybe_on_submit {
+                                Some(ref mut on_submit) => (*on_submit)(plaintext.as_slice()),
+                                None => (),
+                            }
+                            // `to_plaintext` leaves its own heap copy of the
+                            // secret; wipe it too rather than relying solely on
+                            // `SecretBuffer::clear`, which only zeroes the
+                            // original buffer.
+                            let mut bytes = plaintext.into_bytes();
+                            for byte in bytes.iter_mut() { *byte = 0u8; }
+                            self.buffer.clear();
+                        },
+                        _ => (),
+                    }
+                }
+                for ch in text.chars() {
+                    self.buffer.push(ch);
+                }
+            }
+        }
+
+        // Draw the masked character slots.
+        let last_char = self.buffer.as_slice().last().map(|&ch| ch);
+        draw_masked_string(self.uic.win_w, self.uic.win_h, gl, self.uic,
+                           self.pos, self.size, color.plain_contrast(),
+                           self.buffer.len(), last_char, self.reveal_last);
+
+        set_state(self.uic, self.ui_id, new_state,
+                  self.pos.x, self.pos.y, self.width, self.height);
+
+    }
+
+}
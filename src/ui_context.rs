@@ -0,0 +1,229 @@
+
+use freetype as ft;
+use keyboard::KeyboardInput;
+use label::FontSize;
+use mouse_state::MouseState;
+use opengl_graphics::Texture;
+use point::Point;
+use rectangle;
+use std::collections::HashMap;
+use widget::Widget;
+
+/// A widget's bounding box as registered during the `after_layout` phase,
+/// used to resolve topmost-under-mouse once every widget for the frame has
+/// reported its geometry.
+struct Hitbox {
+    ui_id: UIID,
+    pos: Point<f64>,
+    w: f64,
+    h: f64,
+}
+
+/// A unique identifier for a widget.
+pub type UIID = uint;
+
+/// Key used to look a rasterized glyph up in the `UIContext`'s glyph cache.
+#[deriving(PartialEq, Eq, Hash, Clone)]
+pub struct GlyphKey {
+    pub size: FontSize,
+    pub ch: char,
+}
+
+/// A glyph that has already been rasterized and uploaded to the GPU, along
+/// with the metrics needed to lay it out. Stored in `UIContext::glyph_cache`
+/// so that repeated measurement/draw passes over the same text don't
+/// re-rasterize a single glyph more than once.
+pub struct CachedGlyph {
+    pub texture: Texture,
+    pub advance_x: i32,
+    pub advance_y: i32,
+    pub left: i32,
+    pub top: i32,
+}
+
+/// Common state for all widgets held within a single owning `UIContext`.
+pub struct UIContext {
+    pub win_w: f64,
+    pub win_h: f64,
+    mouse: MouseState,
+    /// Keyboard events collected since the start of the current frame.
+    keyboard: KeyboardInput,
+    /// The faces to search for a glyph, in priority order. `faces[0]` is
+    /// the primary face; later faces are only consulted as fallbacks for
+    /// codepoints the primary face doesn't cover.
+    faces: Vec<ft::face::Face<'static>>,
+    /// Caches which face in `faces` covers a given `char`, so the fallback
+    /// search only has to run once per codepoint.
+    face_for_char: HashMap<char, uint>,
+    glyph_cache: HashMap<GlyphKey, CachedGlyph>,
+    widget_data: HashMap<UIID, Widget>,
+    /// Bounding boxes registered this frame via `insert_hitbox`, in
+    /// insertion order, so that overlap resolves to the last (topmost)
+    /// widget drawn rather than whichever widget happens to query first.
+    hitboxes: Vec<Hitbox>,
+}
+
+impl UIContext {
+
+    /// Construct a new `UIContext` with a single primary face.
+    pub fn new(face: ft::face::Face<'static>, win_w: f64, win_h: f64) -> UIContext {
+        UIContext::from_faces(vec![face], win_w, win_h)
+    }
+
+    /// Construct a new `UIContext` with a fallback chain of faces. `faces[0]`
+    /// is used first; later faces are only searched when an earlier face
+    /// doesn't contain the requested codepoint.
+    pub fn from_faces(faces: Vec<ft::face::Face<'static>>, win_w: f64, win_h: f64) -> UIContext {
+        UIContext {
+            win_w: win_w,
+            win_h: win_h,
+            mouse: MouseState::new(),
+            keyboard: KeyboardInput::new(),
+            faces: faces,
+            face_for_char: HashMap::new(),
+            glyph_cache: HashMap::new(),
+            widget_data: HashMap::new(),
+            hitboxes: Vec::new(),
+        }
+    }
+
+    /// Begin a new frame's `after_layout` phase by discarding last frame's
+    /// hitboxes and keyboard events. Must be called once per frame before
+    /// any widget calls `insert_hitbox`, and *before* the frame's
+    /// `handle_key_press`/`handle_text_input` calls for that same frame —
+    /// calling it after them would wipe out the very events it was meant
+    /// to hand to widgets. The expected per-frame order is:
+    /// `after_layout` → `handle_key_press`/`handle_text_input` →
+    /// `register_hitbox` on every widget → `draw` on every widget.
+    pub fn after_layout(&mut self) {
+        self.hitboxes.clear();
+        self.keyboard.clear();
+    }
+
+    /// Record a keyboard event for the current frame. Called by the owning
+    /// application's event loop after `after_layout` and before widgets
+    /// are drawn (see `after_layout`'s call-order note).
+    pub fn handle_key_press(&mut self, key: ::keyboard::Key) {
+        self.keyboard.pressed_keys.push(key);
+    }
+
+    /// Record typed text for the current frame. Called by the owning
+    /// application's event loop after `after_layout` and before widgets
+    /// are drawn (see `after_layout`'s call-order note).
+    pub fn handle_text_input(&mut self, text: &str) {
+        self.keyboard.entered_text.push_str(text);
+    }
+
+    /// Return this frame's keyboard events.
+    pub fn get_keyboard_input(&self) -> &KeyboardInput {
+        &self.keyboard
+    }
+
+    /// Register a widget's bounding box for this frame without painting
+    /// anything. This is the `after_layout` phase: the application must
+    /// call `register_hitbox` (see `Hitboxed`) on *every* widget for the
+    /// frame before calling `draw` on *any* of them, so that every widget's
+    /// `is_topmost_hitbox` query is resolved against the full frame's
+    /// stacking order rather than only the widgets registered so far.
+    pub fn insert_hitbox(&mut self, ui_id: UIID, pos: Point<f64>, w: f64, h: f64) {
+        self.hitboxes.push(Hitbox { ui_id: ui_id, pos: pos, w: w, h: h });
+    }
+
+    /// Whether `ui_id`'s registered hitbox is the topmost one under the
+    /// mouse this frame. Hitboxes are searched back-to-front (last
+    /// inserted wins on overlap), so later-drawn widgets take priority.
+    pub fn is_topmost_hitbox(&self, ui_id: UIID) -> bool {
+        let mouse_pos = self.mouse.pos;
+        match self.hitboxes.iter().rev()
+            .find(|hitbox| rectangle::is_over(hitbox.pos, mouse_pos, hitbox.w, hitbox.h)) {
+            Some(hitbox) => hitbox.ui_id == ui_id,
+            None => false,
+        }
+    }
+
+    /// Return the current `MouseState`.
+    pub fn get_mouse_state(&self) -> MouseState {
+        self.mouse
+    }
+
+    /// Return the `CachedGlyph` for the given `FontSize` and `char`,
+    /// rasterizing and inserting it into the cache on first request. Fonts
+    /// are fixed for the lifetime of the `UIContext`, so the cache is left
+    /// unbounded rather than evicted.
+    pub fn get_character(&mut self, size: FontSize, ch: char) -> &CachedGlyph {
+        let key = GlyphKey { size: size, ch: ch };
+        if !self.glyph_cache.contains_key(&key) {
+            let face_idx = self.face_index_for(ch);
+            let glyph = rasterize_glyph(&mut self.faces[face_idx], size, ch);
+            self.glyph_cache.insert(key.clone(), glyph);
+        }
+        self.glyph_cache.get(&key).unwrap()
+    }
+
+    /// Rasterize the `CachedGlyph` for the given `FontSize` and `char`
+    /// without inserting it into `glyph_cache`. Use this instead of
+    /// `get_character` for text that must never be retained in the shared,
+    /// unbounded glyph cache (e.g. a revealed password character). Goes
+    /// around `face_index_for`'s cache too via `face_index_for_uncached`,
+    /// so a revealed character leaves no trace in any `UIContext` map.
+    pub fn get_character_uncached(&mut self, size: FontSize, ch: char) -> CachedGlyph {
+        let face_idx = self.face_index_for_uncached(ch);
+        rasterize_glyph(&mut self.faces[face_idx], size, ch)
+    }
+
+    /// Find which face in `faces` covers `ch`, searching in priority order
+    /// and caching the result. Falls back to the primary face (whose
+    /// `.notdef` glyph will be rendered) if no face covers the codepoint.
+    fn face_index_for(&mut self, ch: char) -> uint {
+        if let Some(&idx) = self.face_for_char.get(&ch) {
+            return idx;
+        }
+        let idx = self.face_index_for_uncached(ch);
+        self.face_for_char.insert(ch, idx);
+        idx
+    }
+
+    /// Find which face in `faces` covers `ch`, searching in priority order,
+    /// without consulting or populating `face_for_char`. Use this instead
+    /// of `face_index_for` when `ch` itself must not be retained anywhere
+    /// in the `UIContext` (e.g. a revealed password character).
+    fn face_index_for_uncached(&self, ch: char) -> uint {
+        self.faces.iter()
+            .position(|face| face.get_char_index(ch as u64) != 0)
+            .unwrap_or(0u)
+    }
+
+}
+
+/// Implemented by widget builder contexts that participate in the shared
+/// hitbox registry. The owning application must call `register_hitbox` on
+/// every widget for the frame (after `UIContext::after_layout`) and only
+/// then call `draw` on any of them, so `is_topmost_hitbox` reflects the
+/// full frame's stacking order rather than just the widgets drawn so far.
+pub trait Hitboxed {
+    /// Register this widget's bounding box for the current frame.
+    fn register_hitbox(&mut self);
+}
+
+/// Rasterize a single glyph via FreeType and upload it to the GPU,
+/// producing the `CachedGlyph` that will be stored in the glyph cache. If
+/// `face` doesn't contain `ch`, FreeType resolves the lookup to the face's
+/// `.notdef` glyph, so layout width stays deterministic either way.
+fn rasterize_glyph(face: &mut ft::face::Face<'static>, size: FontSize, ch: char) -> CachedGlyph {
+    face.set_pixel_sizes(0, size).unwrap();
+    face.load_char(ch as u64, ft::face::RENDER).unwrap();
+    let glyph = face.glyph();
+    let bitmap = glyph.bitmap();
+    let texture = Texture::from_memory_alpha(
+        bitmap.buffer(),
+        bitmap.width() as u32,
+        bitmap.rows() as u32
+    ).unwrap();
+    CachedGlyph {
+        texture: texture,
+        advance_x: (glyph.advance().x >> 16) as i32,
+        advance_y: (glyph.advance().y >> 16) as i32,
+        left: glyph.bitmap_left(),
+        top: glyph.bitmap_top(),
+    }
+}